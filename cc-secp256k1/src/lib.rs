@@ -1,11 +1,24 @@
-use cc::{CryptoError, Hash, PrivateKey, PublicKey, Signature};
+use cc::{CryptoError, Hash, KeyAgreement, PrivateKey, PublicKey, SharedSecret, Signature};
 
 use lazy_static::lazy_static;
 use rand::{CryptoRng, Rng};
-use secp256k1::{All, Message, Secp256k1, ThirtyTwoByteHash};
+use secp256k1::constants::CURVE_ORDER;
+use secp256k1::ecdh::SharedSecret as EcdhSharedSecret;
+use secp256k1::recovery::{RecoverableSignature, RecoveryId};
+use secp256k1::{All, Message, Secp256k1, Signing, ThirtyTwoByteHash, Verification};
+use sha2::{Digest, Sha256};
 
 use std::convert::TryFrom;
-
+use std::fmt;
+
+// `Secp256k1<All>` carries both the signing and verification precomputation
+// tables. That's convenient as a default, but wasteful for a node that only
+// ever does one of the two (e.g. a verify-only full node paying for signing
+// tables it never uses). The `_with` methods below accept an explicit
+// `Secp256k1<C>` so callers can build a `Secp256k1::signing_only()` or
+// `Secp256k1::verification_only()` context sized to their capability, and
+// re-randomize it for side-channel defense; `ENGINE` remains as the
+// convenience default for the trait-based API.
 lazy_static! {
     static ref ENGINE: Secp256k1<All> = Secp256k1::new();
 }
@@ -16,15 +29,66 @@ pub struct Secp256k1PublicKey(secp256k1::PublicKey);
 
 pub struct Secp256k1Signature(secp256k1::Signature);
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Secp256k1Error(secp256k1::Error);
 
+impl fmt::Display for Secp256k1Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for Secp256k1Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Wraps a `secp256k1` error as the given [`CryptoError`] variant,
+/// preserving it as the `source()` of the returned error.
+fn secp_error(
+    variant: fn(cc::CryptoCause) -> CryptoError,
+    err: secp256k1::Error,
+) -> CryptoError {
+    variant(Box::new(Secp256k1Error(err)))
+}
+
+/// A byte slice didn't have the length a fixed-size wire format requires.
+#[derive(Debug)]
+struct LengthMismatch {
+    expected: usize,
+    actual: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} bytes, got {}", self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for LengthMismatch {}
+
+fn length_error(
+    variant: fn(cc::CryptoCause) -> CryptoError,
+    expected: usize,
+    actual: usize,
+) -> CryptoError {
+    variant(Box::new(LengthMismatch { expected, actual }))
+}
+
 pub struct HashedMessage<'a>(&'a Hash);
 
 pub fn generate_keypair<R: CryptoRng + Rng + ?Sized>(
     rng: &mut R,
 ) -> (Secp256k1PrivateKey, Secp256k1PublicKey) {
-    let (secret_key, public_key) = ENGINE.generate_keypair(rng);
+    generate_keypair_with(&ENGINE, rng)
+}
+
+pub fn generate_keypair_with<C: Signing, R: CryptoRng + Rng + ?Sized>(
+    ctx: &Secp256k1<C>,
+    rng: &mut R,
+) -> (Secp256k1PrivateKey, Secp256k1PublicKey) {
+    let (secret_key, public_key) = ctx.generate_keypair(rng);
 
     (
         Secp256k1PrivateKey(secret_key),
@@ -40,7 +104,8 @@ impl TryFrom<&[u8]> for Secp256k1PrivateKey {
     type Error = CryptoError;
 
     fn try_from(bytes: &[u8]) -> Result<Secp256k1PrivateKey, Self::Error> {
-        let secret_key = secp256k1::SecretKey::from_slice(bytes).map_err(Secp256k1Error)?;
+        let secret_key = secp256k1::SecretKey::from_slice(bytes)
+            .map_err(|e| secp_error(CryptoError::ParsePrivateKey, e))?;
 
         Ok(Secp256k1PrivateKey(secret_key))
     }
@@ -51,16 +116,11 @@ impl PrivateKey<32> for Secp256k1PrivateKey {
     type Signature = Secp256k1Signature;
 
     fn sign_message(&self, msg: &Hash) -> Self::Signature {
-        let msg = Message::from(HashedMessage(msg));
-        let sig = ENGINE.sign(&msg, &self.0);
-
-        Secp256k1Signature(sig)
+        self.sign_message_with(&ENGINE, msg)
     }
 
     fn pub_key(&self) -> Self::PublicKey {
-        let pub_key = secp256k1::PublicKey::from_secret_key(&ENGINE, &self.0);
-
-        Secp256k1PublicKey(pub_key)
+        self.pub_key_with(&ENGINE)
     }
 
     fn to_bytes(&self) -> [u8; 32] {
@@ -71,6 +131,33 @@ impl PrivateKey<32> for Secp256k1PrivateKey {
     }
 }
 
+impl Secp256k1PrivateKey {
+    pub fn sign_message_with<C: Signing>(
+        &self,
+        ctx: &Secp256k1<C>,
+        msg: &Hash,
+    ) -> Secp256k1Signature {
+        let msg = Message::from(HashedMessage(msg));
+        let sig = ctx.sign(&msg, &self.0);
+
+        // ECDSA is malleable: (r, s) and (r, n-s) are both valid. Always
+        // emit the low-S form so signatures can be hashed/deduped safely.
+        let mut sig = Secp256k1Signature(sig);
+        sig.normalize_s();
+
+        sig
+    }
+
+    pub fn pub_key_with<C: Signing>(&self, ctx: &Secp256k1<C>) -> Secp256k1PublicKey {
+        let pub_key = secp256k1::PublicKey::from_secret_key(ctx, &self.0);
+
+        Secp256k1PublicKey(pub_key)
+    }
+}
+
+#[cfg(feature = "serde")]
+cc::impl_serde_fixed_bytes!(Secp256k1PrivateKey);
+
 //
 // PublicKey Impl
 //
@@ -79,7 +166,8 @@ impl TryFrom<&[u8]> for Secp256k1PublicKey {
     type Error = CryptoError;
 
     fn try_from(bytes: &[u8]) -> Result<Secp256k1PublicKey, Self::Error> {
-        let pub_key = secp256k1::PublicKey::from_slice(bytes).map_err(Secp256k1Error)?;
+        let pub_key = secp256k1::PublicKey::from_slice(bytes)
+            .map_err(|e| secp_error(CryptoError::ParsePublicKey, e))?;
 
         Ok(Secp256k1PublicKey(pub_key))
     }
@@ -89,20 +177,49 @@ impl PublicKey<33> for Secp256k1PublicKey {
     type Signature = Secp256k1Signature;
 
     fn verify_signature(&self, msg: &Hash, sig: &Self::Signature) -> Result<(), CryptoError> {
+        self.verify_signature_with(&ENGINE, msg, sig)
+    }
+
+    fn to_bytes(&self) -> [u8; 33] {
+        self.0.serialize()
+    }
+}
+
+impl Secp256k1PublicKey {
+    pub fn verify_signature_with<C: Verification>(
+        &self,
+        ctx: &Secp256k1<C>,
+        msg: &Hash,
+        sig: &Secp256k1Signature,
+    ) -> Result<(), CryptoError> {
         let msg = Message::from(HashedMessage(msg));
 
-        ENGINE
-            .verify(&msg, &sig.0, &self.0)
-            .map_err(Secp256k1Error)?;
+        ctx.verify(&msg, &sig.0, &self.0)
+            .map_err(|e| secp_error(CryptoError::VerificationFailed, e))?;
 
         Ok(())
     }
 
-    fn to_bytes(&self) -> [u8; 33] {
-        self.0.serialize()
+    /// Like [`verify_signature_with`](Self::verify_signature_with), but
+    /// additionally rejects high-S signatures for callers that need
+    /// canonical-only signatures (e.g. consensus-sensitive code).
+    pub fn verify_signature_strict<C: Verification>(
+        &self,
+        ctx: &Secp256k1<C>,
+        msg: &Hash,
+        sig: &Secp256k1Signature,
+    ) -> Result<(), CryptoError> {
+        if !sig.is_low_s() {
+            return Err(CryptoError::NonCanonicalSignature);
+        }
+
+        self.verify_signature_with(ctx, msg, sig)
     }
 }
 
+#[cfg(feature = "serde")]
+cc::impl_serde_fixed_bytes!(Secp256k1PublicKey);
+
 //
 // Signature Impl
 //
@@ -111,7 +228,8 @@ impl TryFrom<&[u8]> for Secp256k1Signature {
     type Error = CryptoError;
 
     fn try_from(bytes: &[u8]) -> Result<Secp256k1Signature, Self::Error> {
-        let sig = secp256k1::Signature::from_compact(bytes).map_err(Secp256k1Error)?;
+        let sig = secp256k1::Signature::from_compact(bytes)
+            .map_err(|e| secp_error(CryptoError::ParseSignature, e))?;
 
         Ok(Secp256k1Signature(sig))
     }
@@ -121,13 +239,7 @@ impl Signature<64> for Secp256k1Signature {
     type PublicKey = Secp256k1PublicKey;
 
     fn verify(&self, msg: &Hash, pub_key: &Self::PublicKey) -> Result<(), CryptoError> {
-        let msg = Message::from(HashedMessage(msg));
-
-        ENGINE
-            .verify(&msg, &self.0, &pub_key.0)
-            .map_err(Secp256k1Error)?;
-
-        Ok(())
+        pub_key.verify_signature_with(&ENGINE, msg, self)
     }
 
     fn to_bytes(&self) -> [u8; 64] {
@@ -135,24 +247,141 @@ impl Signature<64> for Secp256k1Signature {
     }
 }
 
+impl Secp256k1Signature {
+    /// Replaces `s` with `n - s` if it's in the upper half of the curve
+    /// order, so the signature takes the canonical low-S form. ECDSA
+    /// signatures are malleable — for any valid `(r, s)`, `(r, n-s)` is
+    /// also valid — which breaks systems that hash or dedupe signatures.
+    pub fn normalize_s(&mut self) {
+        let mut compact = self.0.serialize_compact();
+
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&compact[32..]);
+
+        if is_high_s(&s) {
+            compact[32..].copy_from_slice(&negate_scalar(&s));
+            self.0 = secp256k1::Signature::from_compact(&compact).expect("still a valid signature");
+        }
+    }
+
+    pub fn is_low_s(&self) -> bool {
+        let compact = self.0.serialize_compact();
+
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&compact[32..]);
+
+        !is_high_s(&s)
+    }
+
+    /// Like [`verify`](cc::Signature::verify), but additionally rejects
+    /// high-S signatures for callers that need canonical-only signatures
+    /// (e.g. consensus-sensitive code).
+    pub fn verify_strict(&self, msg: &Hash, pub_key: &Secp256k1PublicKey) -> Result<(), CryptoError> {
+        pub_key.verify_signature_strict(&ENGINE, msg, self)
+    }
+}
+
+fn is_high_s(s: &[u8; 32]) -> bool {
+    *s > half_curve_order()
+}
+
+fn half_curve_order() -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let mut carry = 0u8;
+
+    for i in 0..32 {
+        let cur = CURVE_ORDER[i];
+        out[i] = (cur >> 1) | (carry << 7);
+        carry = cur & 1;
+    }
+
+    out
+}
+
 //
-// Error Impl
+// Recoverable Signature Impl
 //
 
-impl From<Secp256k1Error> for CryptoError {
-    fn from(err: Secp256k1Error) -> Self {
-        use secp256k1::Error;
-
-        match err.0 {
-            Error::IncorrectSignature => CryptoError::InvalidSignature,
-            Error::InvalidMessage => CryptoError::InvalidLength,
-            Error::InvalidPublicKey => CryptoError::InvalidPublicKey,
-            Error::InvalidSignature => CryptoError::InvalidSignature,
-            Error::InvalidSecretKey => CryptoError::InvalidPrivateKey,
-            Error::InvalidRecoveryId => CryptoError::InvalidSignature,
-            Error::InvalidTweak => CryptoError::Other("secp256k1: bad tweak"),
-            Error::NotEnoughMemory => CryptoError::Other("secp256k1: not enough memory"),
+pub struct Secp256k1RecoverableSignature(RecoverableSignature);
+
+impl Secp256k1PrivateKey {
+    pub fn sign_message_recoverable(&self, msg: &Hash) -> Secp256k1RecoverableSignature {
+        self.sign_message_recoverable_with(&ENGINE, msg)
+    }
+
+    pub fn sign_message_recoverable_with<C: Signing>(
+        &self,
+        ctx: &Secp256k1<C>,
+        msg: &Hash,
+    ) -> Secp256k1RecoverableSignature {
+        let msg = Message::from(HashedMessage(msg));
+        let sig = ctx.sign_recoverable(&msg, &self.0);
+
+        Secp256k1RecoverableSignature(sig)
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1RecoverableSignature {
+    type Error = CryptoError;
+
+    fn try_from(bytes: &[u8]) -> Result<Secp256k1RecoverableSignature, Self::Error> {
+        if bytes.len() != 65 {
+            return Err(length_error(CryptoError::ParseSignature, 65, bytes.len()));
         }
+
+        let recovery_id = RecoveryId::from_i32(i32::from(bytes[64]))
+            .map_err(|e| secp_error(CryptoError::BadRecoveryId, e))?;
+        let sig = RecoverableSignature::from_compact(&bytes[..64], recovery_id)
+            .map_err(|e| secp_error(CryptoError::ParseSignature, e))?;
+
+        Ok(Secp256k1RecoverableSignature(sig))
+    }
+}
+
+impl Secp256k1RecoverableSignature {
+    pub fn recover(&self, msg: &Hash) -> Result<Secp256k1PublicKey, CryptoError> {
+        self.recover_with(&ENGINE, msg)
+    }
+
+    pub fn recover_with<C: Verification>(
+        &self,
+        ctx: &Secp256k1<C>,
+        msg: &Hash,
+    ) -> Result<Secp256k1PublicKey, CryptoError> {
+        let msg = Message::from(HashedMessage(msg));
+        let pub_key = ctx
+            .recover(&msg, &self.0)
+            .map_err(|e| secp_error(CryptoError::VerificationFailed, e))?;
+
+        Ok(Secp256k1PublicKey(pub_key))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let (recovery_id, compact) = self.0.serialize_compact();
+
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&compact);
+        bytes[64] = recovery_id.to_i32() as u8;
+
+        bytes
+    }
+}
+
+//
+// KeyAgreement Impl
+//
+
+impl KeyAgreement for Secp256k1PrivateKey {
+    type PublicKey = Secp256k1PublicKey;
+    type SharedSecret = SharedSecret;
+
+    fn diffie_hellman(&self, their_pub: &Self::PublicKey) -> Self::SharedSecret {
+        let shared = EcdhSharedSecret::new(&their_pub.0, &self.0);
+
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&shared[..]);
+
+        SharedSecret::from(bytes)
     }
 }
 
@@ -172,11 +401,257 @@ impl<'a> ThirtyTwoByteHash for HashedMessage<'a> {
     }
 }
 
+//
+// Schnorr (BIP-340) Impl
+//
+
+const BIP340_CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+const BIP340_NONCE_TAG: &[u8] = b"BIP0340/nonce";
+
+pub struct SchnorrSecp256k1PrivateKey(secp256k1::SecretKey);
+
+pub struct SchnorrSecp256k1PublicKey([u8; 32]);
+
+pub struct SchnorrSecp256k1Signature([u8; 64]);
+
+pub fn generate_schnorr_keypair<R: CryptoRng + Rng + ?Sized>(
+    rng: &mut R,
+) -> (SchnorrSecp256k1PrivateKey, SchnorrSecp256k1PublicKey) {
+    let (secret_key, _) = ENGINE.generate_keypair(rng);
+    let priv_key = SchnorrSecp256k1PrivateKey(secret_key);
+    let pub_key = priv_key.pub_key();
+
+    (priv_key, pub_key)
+}
+
+impl TryFrom<&[u8]> for SchnorrSecp256k1PrivateKey {
+    type Error = CryptoError;
+
+    fn try_from(bytes: &[u8]) -> Result<SchnorrSecp256k1PrivateKey, Self::Error> {
+        let secret_key = secp256k1::SecretKey::from_slice(bytes)
+            .map_err(|e| secp_error(CryptoError::ParsePrivateKey, e))?;
+
+        Ok(SchnorrSecp256k1PrivateKey(secret_key))
+    }
+}
+
+impl PrivateKey<32> for SchnorrSecp256k1PrivateKey {
+    type PublicKey = SchnorrSecp256k1PublicKey;
+    type Signature = SchnorrSecp256k1Signature;
+
+    fn sign_message(&self, msg: &Hash) -> Self::Signature {
+        let msg = msg.to_bytes();
+        let (d, px) = even_y_keypair(&self.0);
+
+        let nonce = tagged_hash(BIP340_NONCE_TAG, &[&d[..], &px[..], &msg[..]].concat());
+        let k = secp256k1::SecretKey::from_slice(&nonce).expect("nonce in curve order");
+        let (k, rx) = even_y_keypair(&k);
+
+        let e = challenge(&rx, &px, &msg);
+
+        // s = k + e * d (mod n)
+        let mut s = secp256k1::SecretKey::from_slice(&e).expect("challenge in curve order");
+        s.mul_assign(&d).expect("scalar mul");
+        s.add_assign(&k).expect("scalar add");
+
+        let mut sig = [0u8; 64];
+        sig[..32].copy_from_slice(&rx);
+        sig[32..].copy_from_slice(&s[..]);
+
+        SchnorrSecp256k1Signature(sig)
+    }
+
+    fn pub_key(&self) -> Self::PublicKey {
+        let (_, px) = even_y_keypair(&self.0);
+
+        SchnorrSecp256k1PublicKey(px)
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&self.0[..]);
+
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for SchnorrSecp256k1PublicKey {
+    type Error = CryptoError;
+
+    fn try_from(bytes: &[u8]) -> Result<SchnorrSecp256k1PublicKey, Self::Error> {
+        if bytes.len() != 32 {
+            return Err(length_error(CryptoError::ParsePublicKey, 32, bytes.len()));
+        }
+
+        let mut x_only = [0u8; 32];
+        x_only.copy_from_slice(bytes);
+
+        // Lifting validates that `x_only` is the x-coordinate of a point on
+        // the curve; reject it otherwise.
+        lift_x(&x_only)?;
+
+        Ok(SchnorrSecp256k1PublicKey(x_only))
+    }
+}
+
+impl PublicKey<32> for SchnorrSecp256k1PublicKey {
+    type Signature = SchnorrSecp256k1Signature;
+
+    fn verify_signature(&self, msg: &Hash, sig: &Self::Signature) -> Result<(), CryptoError> {
+        sig.verify(msg, self)
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl TryFrom<&[u8]> for SchnorrSecp256k1Signature {
+    type Error = CryptoError;
+
+    fn try_from(bytes: &[u8]) -> Result<SchnorrSecp256k1Signature, Self::Error> {
+        if bytes.len() != 64 {
+            return Err(length_error(CryptoError::ParseSignature, 64, bytes.len()));
+        }
+
+        let mut sig = [0u8; 64];
+        sig.copy_from_slice(bytes);
+
+        Ok(SchnorrSecp256k1Signature(sig))
+    }
+}
+
+impl Signature<64> for SchnorrSecp256k1Signature {
+    type PublicKey = SchnorrSecp256k1PublicKey;
+
+    fn verify(&self, msg: &Hash, pub_key: &Self::PublicKey) -> Result<(), CryptoError> {
+        let msg = msg.to_bytes();
+
+        let mut rx = [0u8; 32];
+        rx.copy_from_slice(&self.0[..32]);
+        let mut s_bytes = [0u8; 32];
+        s_bytes.copy_from_slice(&self.0[32..]);
+
+        let p = lift_x(&pub_key.0)?;
+        let e = challenge(&rx, &pub_key.0, &msg);
+
+        let s_point = {
+            let s_key = secp256k1::SecretKey::from_slice(&s_bytes)
+                .map_err(|e| secp_error(CryptoError::VerificationFailed, e))?;
+            secp256k1::PublicKey::from_secret_key(&ENGINE, &s_key)
+        };
+
+        // R = s*G - e*P, checked by combining s*G with (-e)*P.
+        let mut e_p = p;
+        e_p.mul_assign(&ENGINE, &negate_scalar(&e))
+            .map_err(|e| secp_error(CryptoError::VerificationFailed, e))?;
+        let r = s_point
+            .combine(&e_p)
+            .map_err(|e| secp_error(CryptoError::VerificationFailed, e))?;
+
+        if x_only(&r) != rx || !has_even_y(&r) {
+            return Err(secp_error(
+                CryptoError::VerificationFailed,
+                secp256k1::Error::IncorrectSignature,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn to_bytes(&self) -> [u8; 64] {
+        self.0
+    }
+}
+
+fn has_even_y(point: &secp256k1::PublicKey) -> bool {
+    point.serialize()[0] == 0x02
+}
+
+fn x_only(point: &secp256k1::PublicKey) -> [u8; 32] {
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&point.serialize()[1..]);
+
+    x
+}
+
+/// Negates `secret` if its point has odd y, so the even-y invariant BIP-340
+/// relies on holds; returns the (possibly negated) secret alongside the
+/// x-only coordinate of its point.
+fn even_y_keypair(secret: &secp256k1::SecretKey) -> ([u8; 32], [u8; 32]) {
+    let point = secp256k1::PublicKey::from_secret_key(&ENGINE, secret);
+
+    if has_even_y(&point) {
+        let mut d = [0u8; 32];
+        d.copy_from_slice(&secret[..]);
+
+        (d, x_only(&point))
+    } else {
+        let mut neg = secret.clone();
+        neg.negate_assign();
+        let point = secp256k1::PublicKey::from_secret_key(&ENGINE, &neg);
+
+        let mut d = [0u8; 32];
+        d.copy_from_slice(&neg[..]);
+
+        (d, x_only(&point))
+    }
+}
+
+/// Lifts an x-only coordinate to the unique point on the curve with that
+/// x-coordinate and even y, rejecting values that aren't valid coordinates.
+fn lift_x(x: &[u8; 32]) -> Result<secp256k1::PublicKey, CryptoError> {
+    let mut compressed = [0x02u8; 33];
+    compressed[1..].copy_from_slice(x);
+
+    secp256k1::PublicKey::from_slice(&compressed)
+        .map_err(|e| secp_error(CryptoError::ParsePublicKey, e))
+}
+
+fn tagged_hash(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+
+    let mut hasher = Sha256::new();
+    hasher.input(&tag_hash);
+    hasher.input(&tag_hash);
+    hasher.input(msg);
+
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hasher.result());
+
+    out
+}
+
+fn challenge(rx: &[u8; 32], px: &[u8; 32], msg: &[u8; 32]) -> [u8; 32] {
+    tagged_hash(BIP340_CHALLENGE_TAG, &[&rx[..], &px[..], &msg[..]].concat())
+}
+
+fn negate_scalar(scalar: &[u8; 32]) -> [u8; 32] {
+    if *scalar == [0u8; 32] {
+        return *scalar;
+    }
+
+    let mut out = [0u8; 32];
+    let mut borrow = 0i32;
+    for i in (0..32).rev() {
+        let diff = CURVE_ORDER[i] as i32 - scalar[i] as i32 - borrow;
+        if diff < 0 {
+            out[i] = (diff + 256) as u8;
+            borrow = 1;
+        } else {
+            out[i] = diff as u8;
+            borrow = 0;
+        }
+    }
+
+    out
+}
+
 #[cfg(test)]
 mod tests {
-    use super::generate_keypair;
+    use super::{generate_keypair, generate_keypair_with, generate_schnorr_keypair};
 
-    use cc::{Hash, PrivateKey, Signature};
+    use cc::{Hash, KeyAgreement, PrivateKey, PublicKey, Signature};
 
     use rand::rngs::OsRng;
     use sha2::{Digest, Sha256};
@@ -197,4 +672,120 @@ mod tests {
         let sig = priv_key.sign_message(&msg);
         assert!(sig.verify(&msg, &pub_key).is_ok());
     }
+
+    #[test]
+    fn should_verify_own_schnorr_signature() {
+        let mut rng = OsRng::new().expect("OsRng");
+        let (priv_key, pub_key) = generate_schnorr_keypair(&mut rng);
+
+        let msg = {
+            let mut hasher = Sha256::new();
+            hasher.input(b"you can(not) redo");
+            Hash::try_from(&hasher.result()[..32]).expect("msg")
+        };
+
+        let sig = priv_key.sign_message(&msg);
+        assert!(pub_key.verify_signature(&msg, &sig).is_ok());
+    }
+
+    #[test]
+    fn should_recover_same_public_key_from_recoverable_signature() {
+        let mut rng = OsRng::new().expect("OsRng");
+        let (priv_key, pub_key) = generate_keypair(&mut rng);
+
+        let msg = {
+            let mut hasher = Sha256::new();
+            hasher.input(b"you can(not) redo");
+            Hash::try_from(&hasher.result()[..32]).expect("msg")
+        };
+
+        let sig = priv_key.sign_message_recoverable(&msg);
+        let recovered = sig.recover(&msg).expect("recover");
+
+        assert_eq!(recovered.to_bytes(), pub_key.to_bytes());
+    }
+
+    #[test]
+    fn should_derive_same_shared_secret_on_both_sides() {
+        let mut rng = OsRng::new().expect("OsRng");
+        let (alice_priv, alice_pub) = generate_keypair(&mut rng);
+        let (bob_priv, bob_pub) = generate_keypair(&mut rng);
+
+        let alice_secret = alice_priv.diffie_hellman(&bob_pub);
+        let bob_secret = bob_priv.diffie_hellman(&alice_pub);
+
+        assert_eq!(alice_secret.to_bytes(), bob_secret.to_bytes());
+    }
+
+    #[test]
+    fn should_sign_and_verify_with_capability_scoped_contexts() {
+        let signing = secp256k1::Secp256k1::signing_only();
+        let verifying = secp256k1::Secp256k1::verification_only();
+
+        let mut rng = OsRng::new().expect("OsRng");
+        let (priv_key, pub_key) = generate_keypair_with(&signing, &mut rng);
+
+        let msg = {
+            let mut hasher = Sha256::new();
+            hasher.input(b"you can(not) redo");
+            Hash::try_from(&hasher.result()[..32]).expect("msg")
+        };
+
+        let sig = priv_key.sign_message_with(&signing, &msg);
+        assert!(pub_key.verify_signature_with(&verifying, &msg, &sig).is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_roundtrip_private_key_through_json_as_hex() {
+        let mut rng = OsRng::new().expect("OsRng");
+        let (priv_key, _) = generate_keypair(&mut rng);
+
+        let json = serde_json::to_string(&priv_key).expect("serialize");
+        assert_eq!(json, format!("\"{}\"", hex::encode(priv_key.to_bytes())));
+
+        let roundtripped: super::Secp256k1PrivateKey =
+            serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(roundtripped.to_bytes(), priv_key.to_bytes());
+    }
+
+    #[test]
+    fn should_always_sign_with_low_s() {
+        let mut rng = OsRng::new().expect("OsRng");
+        let (priv_key, _) = generate_keypair(&mut rng);
+
+        let msg = {
+            let mut hasher = Sha256::new();
+            hasher.input(b"you can(not) redo");
+            Hash::try_from(&hasher.result()[..32]).expect("msg")
+        };
+
+        let sig = priv_key.sign_message(&msg);
+        assert!(sig.is_low_s());
+    }
+
+    #[test]
+    fn should_reject_high_s_signature_in_strict_verify() {
+        let mut rng = OsRng::new().expect("OsRng");
+        let (priv_key, pub_key) = generate_keypair(&mut rng);
+
+        let msg = {
+            let mut hasher = Sha256::new();
+            hasher.input(b"you can(not) redo");
+            Hash::try_from(&hasher.result()[..32]).expect("msg")
+        };
+
+        let mut compact = priv_key.sign_message(&msg).to_bytes();
+
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&compact[32..]);
+        compact[32..].copy_from_slice(&super::negate_scalar(&s));
+        let high_sig = super::Secp256k1Signature::try_from(&compact[..]).expect("parse");
+
+        assert!(!high_sig.is_low_s());
+        assert!(matches!(
+            high_sig.verify_strict(&msg, &pub_key),
+            Err(cc::CryptoError::NonCanonicalSignature)
+        ));
+    }
 }
@@ -4,14 +4,55 @@ pub mod hash;
 pub use hash::HashValue;
 
 use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+/// The underlying cause of a [`CryptoError`], e.g. the backend's own error
+/// type. Boxed so `cc` stays generic over whatever crypto backend produced
+/// it.
+pub type CryptoCause = Box<dyn StdError + Send + Sync + 'static>;
+
+#[derive(Debug)]
 pub enum CryptoError {
-    InvalidLength,
-    InvalidSignature,
-    InvalidPublicKey,
-    InvalidPrivateKey,
-    Other(&'static str),
+    ParsePrivateKey(CryptoCause),
+    ParsePublicKey(CryptoCause),
+    ParseSignature(CryptoCause),
+    MalformedMessage(CryptoCause),
+    BadRecoveryId(CryptoCause),
+    VerificationFailed(CryptoCause),
+    NonCanonicalSignature,
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::ParsePrivateKey(_) => write!(f, "invalid private key"),
+            CryptoError::ParsePublicKey(_) => write!(f, "invalid public key"),
+            CryptoError::ParseSignature(_) => write!(f, "invalid signature"),
+            CryptoError::MalformedMessage(_) => write!(f, "message is not a valid digest"),
+            CryptoError::BadRecoveryId(_) => write!(f, "invalid signature recovery id"),
+            CryptoError::VerificationFailed(_) => write!(f, "signature verification failed"),
+            CryptoError::NonCanonicalSignature => {
+                write!(f, "signature is not in canonical low-S form")
+            }
+            CryptoError::Unsupported(op) => write!(f, "{} is not supported", op),
+        }
+    }
+}
+
+impl StdError for CryptoError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            CryptoError::ParsePrivateKey(cause)
+            | CryptoError::ParsePublicKey(cause)
+            | CryptoError::ParseSignature(cause)
+            | CryptoError::MalformedMessage(cause)
+            | CryptoError::BadRecoveryId(cause)
+            | CryptoError::VerificationFailed(cause) => Some(cause.as_ref()),
+            CryptoError::NonCanonicalSignature | CryptoError::Unsupported(_) => None,
+        }
+    }
 }
 
 pub trait PrivateKey<const LEN: usize>: for<'a> TryFrom<&'a [u8], Error = CryptoError> {
@@ -41,6 +82,27 @@ pub trait Signature<const LEN: usize>: for<'a> TryFrom<&'a [u8], Error = CryptoE
     fn to_bytes(&self) -> [u8; LEN];
 }
 
+pub trait KeyAgreement {
+    type PublicKey;
+    type SharedSecret;
+
+    fn diffie_hellman(&self, their_pub: &Self::PublicKey) -> Self::SharedSecret;
+}
+
+pub struct SharedSecret([u8; 32]);
+
+impl SharedSecret {
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl From<[u8; 32]> for SharedSecret {
+    fn from(bytes: [u8; 32]) -> Self {
+        SharedSecret(bytes)
+    }
+}
+
 pub trait Crypto<const SK: usize, const PK: usize, const SIG: usize> {
     type PrivateKey: PrivateKey<{ SK }, PublicKey = Self::PublicKey, Signature = Self::Signature>;
     type PublicKey: PublicKey<{ PK }, Signature = Self::Signature>;
@@ -67,6 +129,14 @@ pub trait Crypto<const SK: usize, const PK: usize, const SIG: usize> {
         sig.verify(&msg, &pub_key)?;
         Ok(())
     }
+
+    /// Recovers the signer's public key from a signature over `msg`, for
+    /// schemes that support recovery (e.g. recoverable ECDSA). Schemes
+    /// without a recovery mode should leave this default in place rather
+    /// than fabricating a key.
+    fn recover_public_key(_msg: &[u8], _sig: &[u8]) -> Result<Self::PublicKey, CryptoError> {
+        Err(CryptoError::Unsupported("public key recovery"))
+    }
 }
 
 #[cfg(feature = "proptest")]
@@ -91,3 +161,41 @@ macro_rules! impl_quickcheck_arbitrary {
         }
     };
 }
+
+// Note, as with the upstream `secp256k1` crate's own serde support: this
+// encoding is *not* consensus encoding, just a convenient one for config
+// files and RPC payloads. Human-readable formats (JSON, TOML, ...) get
+// lowercase hex of `to_bytes()`; binary formats get the raw fixed-size
+// bytes.
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! impl_serde_fixed_bytes {
+    ($ty:ident) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let bytes = self.to_bytes();
+
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&hex::encode(&bytes[..]))
+                } else {
+                    serializer.serialize_bytes(&bytes[..])
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes: Vec<u8> = if deserializer.is_human_readable() {
+                    let hex_str = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+                    hex::decode(&hex_str).map_err(serde::de::Error::custom)?
+                } else {
+                    <Vec<u8> as serde::Deserialize>::deserialize(deserializer)?
+                };
+
+                $ty::try_from(bytes.as_slice())
+                    .map_err(|err| serde::de::Error::custom(format!("{:?}", err)))
+            }
+        }
+    };
+}